@@ -3,7 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the "hack" directory of this source tree.
 
+use std::collections::HashMap;
 use std::io;
+use std::ops::Deref;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -21,6 +23,52 @@ use rayon::prelude::*;
 use relative_path::RelativePath;
 use unwrap_ocaml::UnwrapOcaml;
 
+/// The bytes of a source file, owned by us (either a regular read, or
+/// contents supplied by the OCaml test caller).
+///
+/// Every file is read into memory with `fs::read`; files are never
+/// memory-mapped, whatever the `use_mmap` flag says, so each file's contents
+/// are copied onto the heap. A mapped file that another process truncates
+/// while it is mapped (e.g. the user saves a shorter version of it
+/// mid-index) raises `SIGBUS` on the next read of the now-out-of-range
+/// pages, which kills this process by default; `fs::read` can only fail
+/// with a recoverable `io::Error`.
+enum FileContents {
+    Owned(Vec<u8>),
+}
+
+impl Deref for FileContents {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileContents::Owned(v) => v,
+        }
+    }
+}
+
+/// Maps each `RelativePath` prefix to the base directory relative paths
+/// under that prefix should be resolved against, built from the
+/// `(Prefix, PathBuf)` pairs OCaml passes in. A prefix that isn't present
+/// has no files to read, so looking it up yields `None` instead of the
+/// reader having to guess or panic.
+#[derive(Default)]
+struct PrefixMap {
+    base_dirs: HashMap<relative_path::Prefix, PathBuf>,
+}
+
+impl PrefixMap {
+    fn new(base_dirs: Vec<(relative_path::Prefix, PathBuf)>) -> Self {
+        Self {
+            base_dirs: base_dirs.into_iter().collect(),
+        }
+    }
+
+    fn base_dir(&self, prefix: relative_path::Prefix) -> Option<&Path> {
+        self.base_dirs.get(&prefix).map(PathBuf::as_path)
+    }
+}
+
 fn parsed_file_to_file_info(file: oxidized::direct_decl_parser::ParsedFileWithHashes) -> FileInfo {
     let mut info = FileInfo {
         position_free_decl_hash: file_info::HashType(Some(Int64::from(
@@ -143,13 +191,16 @@ fn parsed_file_to_file_info_obr<'a>(file: ParsedFileWithHashes<'a>) -> FileInfo
     info
 }
 ocaml_ffi! {
-    fn batch_index_hackrs_ffi_root_relative_paths_only(
+    fn batch_index_hackrs_ffi_prefixed_paths(
         parser_options: DeclParserOptions,
         deregister_php_stdlib_if_hhi: bool,
-        root: PathBuf,
+        base_dirs: Vec<(relative_path::Prefix, PathBuf)>,
         filenames: Vec<(RelativePath, Option<Option<Vec<u8>>>)>,
+        use_mmap: bool,
     ) -> Vec<(RelativePath, Option<(FileInfo, Int64, Vec<SiAddendum>)>)> {
-        let filenames_and_contents = par_read_file_root_only(&root, filenames).unwrap_ocaml();
+        let prefix_map = PrefixMap::new(base_dirs);
+        let filenames_and_contents =
+            par_read_files(&prefix_map, filenames, use_mmap).unwrap_ocaml();
         filenames_and_contents
             .into_par_iter()
             .map(|(relpath, contents)| {
@@ -210,27 +261,42 @@ ocaml_ffi! {
 // or [("file1.php", Some(Some(present))); ("absent.php", Some(None))] to
 // indicate that the content was supplied by our ocaml caller (used for
 // testing only, since the ocaml TestDisk isn't available to Rust).
-fn par_read_file_root_only(
-    root: &Path,
+// `use_mmap` is currently ignored; see the `FileContents` doc comment. A path
+// whose prefix has no base directory configured in `prefix_map` is reported
+// as missing, so a mixed batch of e.g. root and hhi paths can be indexed in
+// one call.
+fn par_read_files(
+    prefix_map: &PrefixMap,
     filenames: Vec<(RelativePath, Option<Option<Vec<u8>>>)>,
-) -> Result<Vec<(RelativePath, Option<Vec<u8>>)>> {
+    use_mmap: bool,
+) -> Result<Vec<(RelativePath, Option<FileContents>)>> {
     filenames
         .into_par_iter()
         .map(|(relpath, test_contents)| {
             if let Some(test_contents) = test_contents {
-                Ok((relpath, test_contents))
+                Ok((relpath, test_contents.map(FileContents::Owned)))
             } else {
-                let prefix = relpath.prefix();
-                let abspath = match prefix {
-                    relative_path::Prefix::Root => root.join(relpath.path()),
-                    _ => panic!("should only be reading files relative to root"),
+                let abspath = match prefix_map.base_dir(relpath.prefix()) {
+                    Some(base_dir) => base_dir.join(relpath.path()),
+                    None => return Ok((relpath, None)),
                 };
-                match std::fs::read(abspath) {
-                    Ok(text) => Ok((relpath, Some(text))),
-                    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok((relpath, None)),
+                match read_file_contents(&abspath, use_mmap) {
+                    Ok(contents) => Ok((relpath, contents)),
                     Err(e) => Err(e.into()),
                 }
             }
         })
         .collect()
 }
+
+// Reads a single file's contents with `fs::read`, returning `None` if the
+// file doesn't exist. `use_mmap` is ignored -- see the `FileContents` doc
+// comment.
+fn read_file_contents(path: &Path, use_mmap: bool) -> io::Result<Option<FileContents>> {
+    let _ = use_mmap;
+    match std::fs::read(path) {
+        Ok(text) => Ok(Some(FileContents::Owned(text))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -7,6 +7,7 @@ use ffi::Vector;
 use naming_special_names::user_attributes as ua;
 use naming_special_names_rust as naming_special_names;
 use serde::Serialize;
+use thiserror::Error;
 
 use crate::ClassName;
 use crate::TypedValue;
@@ -41,6 +42,148 @@ impl Attribute {
     pub fn is<F: Fn(&str) -> bool>(&self, f: F) -> bool {
         f(self.name.as_str())
     }
+
+    /// The number of arguments this attribute was declared with.
+    pub fn arg_count(&self) -> usize {
+        self.arguments.len()
+    }
+
+    /// The argument at `idx`, if present and a string.
+    pub fn arg_str(&self, idx: usize) -> Option<&[u8]> {
+        match self.arguments.as_ref().get(idx)? {
+            TypedValue::String(s) => Some(s.as_bytes()),
+            _ => None,
+        }
+    }
+
+    /// The argument at `idx`, if present and an int.
+    pub fn arg_int(&self, idx: usize) -> Option<i64> {
+        match self.arguments.as_ref().get(idx)? {
+            TypedValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// All arguments that are strings, in order, skipping any that aren't.
+    pub fn string_args(&self) -> impl Iterator<Item = &[u8]> {
+        self.arguments.as_ref().iter().filter_map(|tv| match tv {
+            TypedValue::String(s) => Some(s.as_bytes()),
+            _ => None,
+        })
+    }
+
+    /// Checks that this attribute's arguments match the shape known for its
+    /// name (e.g. `__Deprecated`'s leading message string, `__Memoize`'s
+    /// keying tokens, `__Native`'s arg constants). Attributes we don't have
+    /// shape knowledge for are considered valid.
+    pub fn validate(&self) -> Result<(), AttrError> {
+        let name = self.name.as_str();
+        if ua::is_native(name) {
+            self.validate_native_args()
+        } else if ua::is_memoized(name) {
+            self.validate_memoize_args()
+        } else if name == ua::DEPRECATED {
+            self.validate_deprecated_args()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn validate_deprecated_args(&self) -> Result<(), AttrError> {
+        let name = self.name.as_str();
+        if self.arg_str(0).is_none() {
+            return Err(AttrError::expected_string(name, 0));
+        }
+        if self.arg_count() > 1 && self.arg_int(1).is_none() {
+            return Err(AttrError::expected_int(name, 1));
+        }
+        if self.arg_count() > 2 {
+            return Err(AttrError::extra_arg(name, 2));
+        }
+        Ok(())
+    }
+
+    fn validate_memoize_args(&self) -> Result<(), AttrError> {
+        let name = self.name.as_str();
+        for idx in 0..self.arg_count() {
+            let arg = self
+                .arg_str(idx)
+                .ok_or_else(|| AttrError::expected_string(name, idx))?;
+            if arg != memoize_arg::KEYED_BY_IC.as_bytes()
+                && arg != memoize_arg::NOT_KEYED_BY_IC_AND_LEAK_IC.as_bytes()
+            {
+                return Err(AttrError::unknown_value(name, idx, arg));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_native_args(&self) -> Result<(), AttrError> {
+        let name = self.name.as_str();
+        for idx in 0..self.arg_count() {
+            let arg = self
+                .arg_str(idx)
+                .ok_or_else(|| AttrError::expected_string(name, idx))?;
+            if arg != native_arg::OP_CODE_IMPL.as_bytes()
+                && arg != native_arg::NO_INJECTION.as_bytes()
+            {
+                return Err(AttrError::unknown_value(name, idx, arg));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Describes why an [Attribute]'s arguments don't match the shape known for
+/// its name.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum AttrError {
+    #[error("{attr}: expected argument {idx} to be a string")]
+    ExpectedString { attr: String, idx: usize },
+
+    #[error("{attr}: expected argument {idx} to be an int")]
+    ExpectedInt { attr: String, idx: usize },
+
+    #[error("{attr}: unexpected argument {idx}")]
+    ExtraArg { attr: String, idx: usize },
+
+    #[error("{attr}: argument {idx} is not a recognized value: {value:?}")]
+    UnknownValue {
+        attr: String,
+        idx: usize,
+        value: String,
+    },
+}
+
+impl AttrError {
+    fn expected_string(attr: &str, idx: usize) -> Self {
+        Self::ExpectedString {
+            attr: attr.to_string(),
+            idx,
+        }
+    }
+
+    fn expected_int(attr: &str, idx: usize) -> Self {
+        Self::ExpectedInt {
+            attr: attr.to_string(),
+            idx,
+        }
+    }
+
+    fn extra_arg(attr: &str, idx: usize) -> Self {
+        Self::ExtraArg {
+            attr: attr.to_string(),
+            idx,
+        }
+    }
+
+    fn unknown_value(attr: &str, idx: usize, value: &[u8]) -> Self {
+        Self::UnknownValue {
+            attr: attr.to_string(),
+            idx,
+            value: String::from_utf8_lossy(value).into_owned(),
+        }
+    }
 }
 
 fn is(s: &str, attr: &Attribute) -> bool {
@@ -63,31 +206,24 @@ pub fn is_native_opcode_impl(attrs: impl AsRef<[Attribute]>) -> bool {
 }
 
 fn is_native_arg(s: &str, attrs: impl AsRef<[Attribute]>) -> bool {
-    attrs.as_ref().iter().any(|attr| {
-        attr.is(ua::is_native)
-            && attr.arguments.as_ref().iter().any(|tv| match *tv {
-                TypedValue::String(s0) => s0.as_bytes() == s.as_bytes(),
-                _ => false,
-            })
-    })
+    attrs
+        .as_ref()
+        .iter()
+        .any(|attr| attr.is(ua::is_native) && attr.string_args().any(|s0| s0 == s.as_bytes()))
 }
 
 fn is_memoize_with(attrs: impl AsRef<[Attribute]>, arg: &str) -> bool {
     attrs.as_ref().iter().any(|attr| {
-        ua::is_memoized(attr.name.as_str())
-            && attr.arguments.as_ref().iter().any(|tv| match *tv {
-                TypedValue::String(s0) => s0.as_bytes() == arg.as_bytes(),
-                _ => false,
-            })
+        ua::is_memoized(attr.name.as_str()) && attr.string_args().any(|s0| s0 == arg.as_bytes())
     })
 }
 
 pub fn is_keyed_by_ic_memoize(attrs: impl AsRef<[Attribute]>) -> bool {
-    is_memoize_with(attrs, "KeyedByIC")
+    is_memoize_with(attrs, memoize_arg::KEYED_BY_IC)
 }
 
 pub fn is_not_keyed_by_ic_and_leak_ic(attrs: impl AsRef<[Attribute]>) -> bool {
-    is_memoize_with(attrs, "NotKeyedByICAndLeakIC__DO_NOT_USE")
+    is_memoize_with(attrs, memoize_arg::NOT_KEYED_BY_IC_AND_LEAK_IC)
 }
 
 fn is_foldable(attr: &Attribute) -> bool {
@@ -185,6 +321,11 @@ pub mod native_arg {
     pub const NO_INJECTION: &str = "NoInjection";
 }
 
+pub mod memoize_arg {
+    pub const KEYED_BY_IC: &str = "KeyedByIC";
+    pub const NOT_KEYED_BY_IC_AND_LEAK_IC: &str = "NotKeyedByICAndLeakIC__DO_NOT_USE";
+}
+
 #[cfg(test)]
 mod tests {
     use naming_special_names::user_attributes as ua;
@@ -215,4 +356,44 @@ mod tests {
             .any(|a| a.name.as_str() == ua::DYNAMICALLY_CALLABLE);
         assert!(has_result);
     }
+
+    #[test]
+    fn validate_deprecated_accepts_message_and_optional_rate() {
+        let msg_only = Attribute::new(ua::DEPRECATED, vec![TypedValue::String("bye".into())]);
+        assert!(msg_only.validate().is_ok());
+
+        let msg_and_rate = Attribute::new(
+            ua::DEPRECATED,
+            vec![TypedValue::String("bye".into()), TypedValue::Int(100)],
+        );
+        assert!(msg_and_rate.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_deprecated_rejects_non_string_message() {
+        let attr = Attribute::new(ua::DEPRECATED, vec![TypedValue::Int(1)]);
+        assert_eq!(
+            attr.validate(),
+            Err(AttrError::expected_string(ua::DEPRECATED, 0))
+        );
+    }
+
+    #[test]
+    fn validate_memoize_rejects_unknown_token() {
+        let attr = Attribute::new(ua::MEMOIZE, vec![TypedValue::String("Bogus".into())]);
+        assert_eq!(
+            attr.validate(),
+            Err(AttrError::unknown_value(ua::MEMOIZE, 0, b"Bogus"))
+        );
+    }
+
+    #[test]
+    fn arg_str_and_string_args() {
+        let attr = Attribute::new(
+            "__Native",
+            vec![TypedValue::String(native_arg::NO_INJECTION.into())],
+        );
+        assert_eq!(attr.arg_str(0), Some(native_arg::NO_INJECTION.as_bytes()));
+        assert_eq!(attr.string_args().count(), 1);
+    }
 }
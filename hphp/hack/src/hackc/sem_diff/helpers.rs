@@ -77,15 +77,129 @@ impl MapName for hhbc::UpperBound {
     }
 }
 
+/// The category of discrepancy recorded by a [Diff]. The `lhs`/`rhs` fields
+/// on the [Diff] itself carry the detail for whichever sides apply to that
+/// kind: debug-rendered values or keys, a nested comparator's error message
+/// (in `lhs`, for a [DiffKind::ValueMismatch] from an `f_eq`), or which side
+/// is longer (in `lhs`, for a [DiffKind::LengthMismatch]).
+#[derive(Clone, Debug)]
+pub(crate) enum DiffKind {
+    ValueMismatch,
+    MissingKey,
+    ExtraKey,
+    LengthMismatch,
+}
+
+/// A single semantic discrepancy found while comparing two HHBC units.
+/// Collected into a [DiffSink] instead of aborting the comparison, so one
+/// helper call reports every mismatch among the items it walks.
+#[derive(Clone, Debug)]
+pub(crate) struct Diff {
+    pub path: String,
+    pub kind: DiffKind,
+    pub lhs: String,
+    pub rhs: String,
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            DiffKind::ValueMismatch => {
+                write!(f, "Mismatch in {}:\n{}\n{}", self.path, self.lhs, self.rhs)
+            }
+            DiffKind::MissingKey => write!(
+                f,
+                "In {} lhs has key {} but rhs does not",
+                self.path, self.lhs
+            ),
+            DiffKind::ExtraKey => write!(
+                f,
+                "In {} rhs has key {} but lhs does not",
+                self.path, self.rhs
+            ),
+            DiffKind::LengthMismatch => write!(f, "Mismatch in {}: {}", self.path, self.lhs),
+        }
+    }
+}
+
+/// Accumulates [Diff]s found while walking two structures in parallel,
+/// instead of bailing out on the first mismatch.
+///
+/// The `f_eq` comparators passed to `sem_diff_map_t_into`,
+/// `sem_diff_option_into` and `sem_diff_iter_into` return a plain
+/// `Result<()>`. A failing comparator is recorded as one
+/// [DiffKind::ValueMismatch] at that item's path, with the comparator's
+/// error text in `lhs`; the individual sub-field differences inside it are
+/// not recorded as separate [Diff]s. A comparator that chains several checks
+/// with `?` stops at its first failing check, so the checks after it are
+/// not reported.
+#[derive(Default)]
+pub(crate) struct DiffSink {
+    diffs: Vec<Diff>,
+}
+
+impl DiffSink {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, path: &CodePath<'_>, kind: DiffKind, lhs: String, rhs: String) {
+        self.diffs.push(Diff {
+            path: path.to_string(),
+            kind,
+            lhs,
+            rhs,
+        });
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.diffs.is_empty()
+    }
+
+    pub(crate) fn diffs(&self) -> &[Diff] {
+        &self.diffs
+    }
+
+    /// Collapses every recorded [Diff] into the legacy `Result<()>` shape,
+    /// so callers that only want a pass/fail answer are unaffected.
+    fn into_result(self) -> Result<()> {
+        if self.diffs.is_empty() {
+            Ok(())
+        } else {
+            let report = self
+                .diffs
+                .iter()
+                .map(Diff::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+            bail!("{}", report);
+        }
+    }
+}
+
 pub(crate) fn sem_diff_eq<Ta, Tb>(path: &CodePath<'_>, a: &Ta, b: &Tb) -> Result<()>
+where
+    Ta: PartialEq<Tb> + fmt::Debug,
+    Tb: fmt::Debug,
+{
+    let mut sink = DiffSink::new();
+    sem_diff_eq_into(&mut sink, path, a, b);
+    sink.into_result()
+}
+
+pub(crate) fn sem_diff_eq_into<Ta, Tb>(sink: &mut DiffSink, path: &CodePath<'_>, a: &Ta, b: &Tb)
 where
     Ta: PartialEq<Tb> + fmt::Debug,
     Tb: fmt::Debug,
 {
     if a != b {
-        bail!("Mismatch in {}:\n{:?}\n{:?}", path, a, b);
+        sink.push(
+            path,
+            DiffKind::ValueMismatch,
+            format!("{:?}", a),
+            format!("{:?}", b),
+        );
     }
-    Ok(())
 }
 
 pub(crate) fn sem_diff_map_t<'a, 'b, Ta, Tb, F>(
@@ -98,43 +212,88 @@ where
     Ta: MapName + 'a,
     Tb: MapName + 'b,
     F: Fn(&CodePath<'_>, &'a Ta, &'b Tb) -> Result<()>,
+{
+    let mut sink = DiffSink::new();
+    sem_diff_map_t_into(&mut sink, path, a, b, f_eq);
+    sink.into_result()
+}
+
+pub(crate) fn sem_diff_map_t_into<'a, 'b, Ta, Tb, F>(
+    sink: &mut DiffSink,
+    path: &CodePath<'_>,
+    a: &'a [Ta],
+    b: &'b [Tb],
+    f_eq: F,
+) where
+    Ta: MapName + 'a,
+    Tb: MapName + 'b,
+    F: Fn(&CodePath<'_>, &'a Ta, &'b Tb) -> Result<()>,
 {
     let a_hash: HashMap<&str, &Ta> = a.iter().map(|t| (t.get_name(), t)).collect();
     let b_hash: HashMap<&str, &Tb> = b.iter().map(|t| (t.get_name(), t)).collect();
     let a_keys: HashSet<&str> = a_hash.keys().copied().collect();
     let b_keys: HashSet<&str> = b_hash.keys().copied().collect();
+
     for k in &a_keys & &b_keys {
-        f_eq(&path.index_str(k), a_hash[k], b_hash[k])?;
+        let item_path = path.index_str(k);
+        if let Err(e) = f_eq(&item_path, a_hash[k], b_hash[k]) {
+            sink.push(
+                &item_path,
+                DiffKind::ValueMismatch,
+                e.to_string(),
+                String::new(),
+            );
+        }
     }
 
-    if let Some(k) = (&a_keys - &b_keys).into_iter().next() {
-        bail!("In {} lhs has key {} but rhs does not", path, k.to_string());
+    for k in &a_keys - &b_keys {
+        sink.push(path, DiffKind::MissingKey, k.to_string(), String::new());
     }
 
-    if let Some(k) = (&b_keys - &a_keys).into_iter().next() {
-        bail!("In {} rhs has key {} but lhs does not", path, k.to_string());
+    for k in &b_keys - &a_keys {
+        sink.push(path, DiffKind::ExtraKey, String::new(), k.to_string());
     }
-
-    Ok(())
 }
 
 #[allow(dead_code)]
 pub(crate) fn sem_diff_set_t<'a, T>(path: &CodePath<'_>, a: &'a [T], b: &'a [T]) -> Result<()>
 where
     T: std::hash::Hash + Eq + std::fmt::Debug,
+{
+    let mut sink = DiffSink::new();
+    sem_diff_set_t_into(&mut sink, path, a, b);
+    sink.into_result()
+}
+
+#[allow(dead_code)]
+pub(crate) fn sem_diff_set_t_into<'a, T>(
+    sink: &mut DiffSink,
+    path: &CodePath<'_>,
+    a: &'a [T],
+    b: &'a [T],
+) where
+    T: std::hash::Hash + Eq + std::fmt::Debug,
 {
     let a_keys: HashSet<&T> = a.iter().collect();
     let b_keys: HashSet<&T> = b.iter().collect();
 
-    if let Some(k) = (&a_keys - &b_keys).into_iter().next() {
-        bail!("In {} lhs has value {:?} but rhs does not", path, k);
+    for k in &a_keys - &b_keys {
+        sink.push(
+            path,
+            DiffKind::MissingKey,
+            format!("{:?}", k),
+            String::new(),
+        );
     }
 
-    if let Some(k) = (&b_keys - &a_keys).into_iter().next() {
-        bail!("In {} rhs has value {:?} but lhs does not", path, k);
+    for k in &b_keys - &a_keys {
+        sink.push(
+            path,
+            DiffKind::ExtraKey,
+            String::new(),
+            format!("{:?}", k),
+        );
     }
-
-    Ok(())
 }
 
 pub(crate) fn sem_diff_option<T, F>(
@@ -146,36 +305,107 @@ pub(crate) fn sem_diff_option<T, F>(
 where
     T: fmt::Debug,
     F: FnOnce(&CodePath<'_>, &T, &T) -> Result<()>,
+{
+    let mut sink = DiffSink::new();
+    sem_diff_option_into(&mut sink, path, a, b, f_eq);
+    sink.into_result()
+}
+
+pub(crate) fn sem_diff_option_into<T, F>(
+    sink: &mut DiffSink,
+    path: &CodePath<'_>,
+    a: Option<&T>,
+    b: Option<&T>,
+    f_eq: F,
+) where
+    T: fmt::Debug,
+    F: FnOnce(&CodePath<'_>, &T, &T) -> Result<()>,
 {
     match (a, b) {
-        (None, None) => Ok(()),
-        (Some(inner), None) => bail!("Mismatch in {}:\nSome({:?})\nNone", path, inner),
-        (None, Some(inner)) => bail!("Mismatch in {}:\nNone\nSome({:?})", path, inner),
-        (Some(lhs), Some(rhs)) => f_eq(&path.qualified("unwrap()"), lhs, rhs),
+        (None, None) => {}
+        (Some(inner), None) => sink.push(
+            path,
+            DiffKind::ValueMismatch,
+            format!("Some({:?})", inner),
+            "None".to_string(),
+        ),
+        (None, Some(inner)) => sink.push(
+            path,
+            DiffKind::ValueMismatch,
+            "None".to_string(),
+            format!("Some({:?})", inner),
+        ),
+        (Some(lhs), Some(rhs)) => {
+            let inner_path = path.qualified("unwrap()");
+            if let Err(e) = f_eq(&inner_path, lhs, rhs) {
+                sink.push(
+                    &inner_path,
+                    DiffKind::ValueMismatch,
+                    e.to_string(),
+                    String::new(),
+                );
+            }
+        }
     }
 }
 
 pub(crate) fn sem_diff_iter<'a, V: 'a, F>(
     path: &CodePath<'_>,
-    mut a: impl Iterator<Item = V>,
-    mut b: impl Iterator<Item = V>,
+    a: impl Iterator<Item = V>,
+    b: impl Iterator<Item = V>,
     f_eq: F,
 ) -> Result<()>
 where
     F: Fn(&CodePath<'_>, V, V) -> Result<()>,
+{
+    let mut sink = DiffSink::new();
+    sem_diff_iter_into(&mut sink, path, a, b, f_eq);
+    sink.into_result()
+}
+
+pub(crate) fn sem_diff_iter_into<'a, V: 'a, F>(
+    sink: &mut DiffSink,
+    path: &CodePath<'_>,
+    mut a: impl Iterator<Item = V>,
+    mut b: impl Iterator<Item = V>,
+    f_eq: F,
+) where
+    F: Fn(&CodePath<'_>, V, V) -> Result<()>,
 {
     let mut idx = 0;
     loop {
         let ai = a.next();
         let bi = b.next();
         match (ai, bi) {
-            (None, None) => return Ok(()),
-            (Some(av), Some(bv)) => f_eq(&path.index(idx), av, bv)?,
+            (None, None) => return,
+            (Some(av), Some(bv)) => {
+                let item_path = path.index(idx);
+                if let Err(e) = f_eq(&item_path, av, bv) {
+                    sink.push(
+                        &item_path,
+                        DiffKind::ValueMismatch,
+                        e.to_string(),
+                        String::new(),
+                    );
+                }
+            }
             (Some(_), None) => {
-                bail!("Mismatch in {}: A side is longer.", path);
+                sink.push(
+                    path,
+                    DiffKind::LengthMismatch,
+                    "A side is longer.".to_string(),
+                    String::new(),
+                );
+                return;
             }
             (None, Some(_)) => {
-                bail!("Mismatch in {}: B side is longer.", path);
+                sink.push(
+                    path,
+                    DiffKind::LengthMismatch,
+                    "B side is longer.".to_string(),
+                    String::new(),
+                );
+                return;
             }
         }
         idx += 1;